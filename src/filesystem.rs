@@ -1,13 +1,20 @@
 use std::path::PathBuf;
 
-pub async fn get_hoi_folder() -> Result<PathBuf, String> {
+use tauri::{Manager, Runtime};
+
+use crate::SteamWorks;
+
+/// Locate the install folder of the game configured via [`crate::init_for_app`].
+pub async fn get_game_folder<R: Runtime>(app: tauri::AppHandle<R>) -> Result<PathBuf, String> {
+    let app_id = app.state::<SteamWorks>().app_id;
+
     let Some(mut steam_dir) = steamlocate::SteamDir::locate() else {
         return Err("Could not find steam directory".to_string());
     };
 
     let libraryfolders = steam_dir.libraryfolders();
     for libraryfolder in &libraryfolders.paths {
-        let appmanifest_path = libraryfolder.join(format!("appmanifest_{}.acf", 394360));
+        let appmanifest_path = libraryfolder.join(format!("appmanifest_{}.acf", app_id));
         if appmanifest_path.is_file() {
             let content = tokio::fs::read_to_string(&appmanifest_path).await.unwrap();
             let Some(cps) = regex::Regex::new(r#"installdir"\s+"(.+?)"\n?"#)
@@ -22,17 +29,23 @@ pub async fn get_hoi_folder() -> Result<PathBuf, String> {
 
             let path = libraryfolder.join("common").join(p);
             if !path.is_dir() {
-                return Err("Could not find hoi directory".to_string());
+                return Err("Could not find game directory".to_string());
             };
 
             return Ok(path);
         }
     }
-    Err("Could not find hoi directory".to_string())
+    Err("Could not find game directory".to_string())
 }
 
-pub async fn start_game(path: &PathBuf, options: Vec<String>) -> Result<(), String> {
-    let game = path.join("hoi4.exe");
+pub async fn start_game<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    path: &PathBuf,
+    options: Vec<String>,
+) -> Result<(), String> {
+    let exe_name = app.state::<SteamWorks>().exe_name.clone();
+
+    let game = path.join(exe_name);
     if !game.is_file() {
         return Err("Could not find game".to_string());
     };
@@ -7,22 +7,47 @@ extern crate log;
 
 pub mod workshop;
 pub mod filesystem;
+pub mod presence;
+
+/// The HOI4 Steam app id, used by [`init`] as the default for [`init_for_app`].
+const HOI4_APP_ID: u32 = 394360;
+/// The HOI4 executable name, used by [`init`] as the default for [`init_for_app`].
+const HOI4_EXE_NAME: &str = "hoi4.exe";
 
 /// The plugin state.
 pub struct SteamWorks {
     pub client: std::sync::Mutex<steamworks::Client>,
     pub single_client: std::sync::Mutex<steamworks::SingleClient>,
+    /// Workshop items whose download is currently in flight, polled for
+    /// progress on every callback tick.
+    pub downloads: std::sync::Mutex<std::collections::HashSet<steamworks::PublishedFileId>>,
+    /// The Steam app id this plugin instance was configured for.
+    pub app_id: u32,
+    /// The game executable to launch via [`filesystem::start_game`].
+    pub exe_name: String,
 }
 
-/// Initializes the plugin.
+/// Initializes the plugin for HOI4.
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    init_for_app(HOI4_APP_ID, HOI4_EXE_NAME)
+}
+
+/// Initializes the plugin for an arbitrary Steam app, gating the workshop
+/// and game-launch commands behind the given app id and executable name
+/// instead of the HOI4 defaults.
+pub fn init_for_app<R: Runtime>(app_id: u32, exe_name: &str) -> TauriPlugin<R> {
+    let exe_name = exe_name.to_string();
+
     Builder::new("hal-steamworks")
-        .setup(|app| {
-            let (client, single) = steamworks::Client::init_app(394360)?;
+        .setup(move |app| {
+            let (client, single) = steamworks::Client::init_app(app_id)?;
 
             app.manage(SteamWorks {
                 client: std::sync::Mutex::new(client),
                 single_client: std::sync::Mutex::new(single),
+                downloads: std::sync::Mutex::new(std::collections::HashSet::new()),
+                app_id,
+                exe_name,
             });
 
             let handle = app.app_handle();
@@ -37,7 +62,19 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                 trace!("running callbacks");
                 sc.run_callbacks();
             }
+
+            workshop::poll_download_progress(app);
         })
-        .invoke_handler(tauri::generate_handler![workshop::get_workshop_item])
+        .invoke_handler(tauri::generate_handler![
+            workshop::get_workshop_item,
+            workshop::get_workshop_collection,
+            workshop::search_workshop_items,
+            workshop::subscribe_workshop_item,
+            workshop::unsubscribe_workshop_item,
+            workshop::download_workshop_item,
+            presence::set_rich_presence,
+            presence::clear_rich_presence,
+            presence::get_current_user
+        ])
         .build()
 }
@@ -0,0 +1,66 @@
+use tauri::{Manager, Runtime};
+
+use crate::SteamWorks;
+
+/// The currently logged in Steam user.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SteamUser {
+    pub steam_id: u64,
+    pub persona_name: String,
+}
+
+/// Set a Steam Rich Presence key/value pair, shown to friends in the Steam
+/// friends list.
+/// ```ts
+/// import { invoke } from "@tauri-apps/api";
+///
+/// await invoke("set-rich-presence", { key: "steam_display", value: "#StatusFormat" });
+/// ```
+#[tauri::command]
+pub(crate) fn set_rich_presence<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    let state = app.state::<SteamWorks>();
+    let client = state.client.lock().map_err(|_| "client is null".to_string())?;
+
+    client.friends().set_rich_presence(&key, Some(&value));
+
+    Ok(())
+}
+
+/// Clear all Steam Rich Presence keys set by this process.
+/// ```ts
+/// import { invoke } from "@tauri-apps/api";
+///
+/// await invoke("clear-rich-presence");
+/// ```
+#[tauri::command]
+pub(crate) fn clear_rich_presence<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<SteamWorks>();
+    let client = state.client.lock().map_err(|_| "client is null".to_string())?;
+
+    client.friends().clear_rich_presence();
+
+    Ok(())
+}
+
+/// Get the currently logged in Steam user's id and persona name.
+/// ```ts
+/// import { invoke } from "@tauri-apps/api";
+///
+/// await invoke("get-current-user");
+/// ```
+#[tauri::command]
+pub(crate) fn get_current_user<R: Runtime>(app: tauri::AppHandle<R>) -> Result<SteamUser, String> {
+    let state = app.state::<SteamWorks>();
+    let client = state.client.lock().map_err(|_| "client is null".to_string())?;
+
+    let friends = client.friends();
+
+    Ok(SteamUser {
+        steam_id: friends.steam_id().raw(),
+        persona_name: friends.name(),
+    })
+}
@@ -1,8 +1,9 @@
-use std::{path::PathBuf, sync::{Arc, Condvar, Mutex}};
+use std::path::PathBuf;
 
 use log::error;
-use steamworks::{ItemState, PublishedFileId};
+use steamworks::{AppId, ItemState, PublishedFileId, UGCQueryType, UGCType};
 use tauri::{Event, Manager, Runtime};
+use tokio::sync::oneshot;
 
 use crate::SteamWorks;
 
@@ -21,6 +22,233 @@ pub struct LocalWorkshopItem {
     pub size_on_disk: u64,
 }
 
+/// Progress of an in-flight workshop item download, emitted on
+/// `workshop-download-progress`/`workshop-download-complete`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WorkshopDownloadProgress {
+    pub id: u64,
+    pub downloaded: u64,
+    pub total: u64,
+    pub state: u32,
+}
+
+/// How to rank a [`WorkshopSearchQuery`]'s results.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub enum WorkshopSearchSort {
+    #[default]
+    TextRelevance,
+    Trend,
+    Vote,
+    PublicationDate,
+    TotalUniqueSubscriptions,
+}
+
+impl From<WorkshopSearchSort> for UGCQueryType {
+    fn from(sort: WorkshopSearchSort) -> Self {
+        match sort {
+            WorkshopSearchSort::TextRelevance => UGCQueryType::RankedByTextSearch,
+            WorkshopSearchSort::Trend => UGCQueryType::RankedByTrend,
+            WorkshopSearchSort::Vote => UGCQueryType::RankedByVote,
+            WorkshopSearchSort::PublicationDate => UGCQueryType::RankedByPublicationDate,
+            WorkshopSearchSort::TotalUniqueSubscriptions => {
+                UGCQueryType::RankedByTotalUniqueSubscriptions
+            }
+        }
+    }
+}
+
+/// A workshop search request.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkshopSearchQuery {
+    pub text: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub match_any_tag: bool,
+    #[serde(default)]
+    pub sort: WorkshopSearchSort,
+    pub page: u32,
+}
+
+/// A page of [`WokrshopItem`]s matching a [`WorkshopSearchQuery`], plus the
+/// total number of results across all pages.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WorkshopSearchResults {
+    pub items: Vec<WokrshopItem>,
+    pub total: u32,
+}
+
+/// Query a single workshop item by id and map it to a [`WokrshopItem`].
+///
+/// Returns a future that resolves once the steamworks callback fires,
+/// without holding any lock across the await. Shared by the event-based
+/// `need_workshop_item` and the command-based `get_workshop_item` so they
+/// don't duplicate the query/mapping logic.
+fn query_workshop_item(
+    ugc: steamworks::UGC<steamworks::ClientManager>,
+    id: PublishedFileId,
+) -> impl std::future::Future<Output = Result<WokrshopItem, String>> {
+    let (tx, rx) = oneshot::channel();
+
+    let query = ugc.query_item(id).map(move |query| {
+        query.fetch(move |x| {
+            let result = match x {
+                Ok(info) => {
+                    let preview = info.preview_url(0);
+                    info.get(0)
+                        .map(|item| WokrshopItem {
+                            id: item.published_file_id.0,
+                            name: item.title,
+                            description: Some(item.description),
+                            preview,
+                        })
+                        .ok_or_else(|| "item is null".to_string())
+                }
+                Err(err) => Err(err.to_string()),
+            };
+
+            let _ = tx.send(result);
+        });
+    });
+
+    async move {
+        query.map_err(|err| format!("Cannot query item: {err}"))?;
+        rx.await.map_err(|_| "Query callback was dropped".to_string())?
+    }
+}
+
+/// Query a batch of workshop items in one request, mapping each result to a
+/// [`WokrshopItem`] by index. Used to resolve a collection's children after
+/// [`query_workshop_collection_children`] has listed their ids.
+fn query_workshop_items(
+    ugc: steamworks::UGC<steamworks::ClientManager>,
+    ids: Vec<PublishedFileId>,
+) -> impl std::future::Future<Output = Result<Vec<WokrshopItem>, String>> {
+    let (tx, rx) = oneshot::channel();
+    let count = ids.len() as u32;
+
+    let query = ugc.query_items(ids).map(move |query| {
+        query.fetch(move |x| {
+            let result = match x {
+                Ok(info) => Ok((0..count)
+                    .filter_map(|i| {
+                        let preview = info.preview_url(i);
+                        info.get(i).map(|item| WokrshopItem {
+                            id: item.published_file_id.0,
+                            name: item.title,
+                            description: Some(item.description),
+                            preview,
+                        })
+                    })
+                    .collect::<Vec<_>>()),
+                Err(err) => Err(err.to_string()),
+            };
+
+            let _ = tx.send(result);
+        });
+    });
+
+    async move {
+        query.map_err(|err| format!("Cannot query items: {err}"))?;
+        rx.await.map_err(|_| "Query callback was dropped".to_string())?
+    }
+}
+
+/// Steam's `query_items` batch query silently truncates past this many ids
+/// per call, so [`get_workshop_collection`] chunks large collections into
+/// batches of at most this size.
+const MAX_QUERY_ITEMS_BATCH: usize = 50;
+
+/// Query a collection by its own id with child inclusion enabled, returning
+/// the published file ids of its members for [`query_workshop_items`] to
+/// resolve into full [`WokrshopItem`]s.
+fn query_workshop_collection_children(
+    ugc: steamworks::UGC<steamworks::ClientManager>,
+    id: PublishedFileId,
+) -> impl std::future::Future<Output = Result<Vec<PublishedFileId>, String>> {
+    let (tx, rx) = oneshot::channel();
+
+    let query = ugc.query_item(id).map(move |query| {
+        query.include_children(true).fetch(move |x| {
+            let result = x
+                .map(|info| info.children(0).unwrap_or_default())
+                .map_err(|err| err.to_string());
+
+            let _ = tx.send(result);
+        });
+    });
+
+    async move {
+        query.map_err(|err| format!("Cannot query collection: {err}"))?;
+        rx.await.map_err(|_| "Query callback was dropped".to_string())?
+    }
+}
+
+/// Run a [`WorkshopSearchQuery`] against the UGC "query all" API, returning
+/// the matching page of [`WokrshopItem`]s alongside the total result count.
+///
+/// `TextRelevance` ranking needs a non-empty search string to mean anything;
+/// a tag-only browse (empty `text`) falls back to `PublicationDate` instead
+/// of running a text-relevance query against an empty string.
+fn query_workshop_search(
+    ugc: steamworks::UGC<steamworks::ClientManager>,
+    app_id: AppId,
+    query: WorkshopSearchQuery,
+) -> impl std::future::Future<Output = Result<WorkshopSearchResults, String>> {
+    let (tx, rx) = oneshot::channel();
+
+    let sort = if query.text.is_empty() && matches!(query.sort, WorkshopSearchSort::TextRelevance)
+    {
+        WorkshopSearchSort::PublicationDate
+    } else {
+        query.sort.clone()
+    };
+
+    let result = ugc
+        .query_all(sort.into(), UGCType::Items, app_id, app_id, query.page)
+        .map(move |handle| {
+            let handle = handle
+                .search_text(&query.text)
+                .match_any_tag(query.match_any_tag);
+            let handle = query
+                .tags
+                .iter()
+                .fold(handle, |handle, tag| handle.required_tag(tag));
+
+            handle.fetch(move |x| {
+                let result = match x {
+                    Ok(info) => {
+                        let items = (0..info.returned_results())
+                            .filter_map(|i| {
+                                let preview = info.preview_url(i);
+                                info.get(i).map(|item| WokrshopItem {
+                                    id: item.published_file_id.0,
+                                    name: item.title,
+                                    description: Some(item.description),
+                                    preview,
+                                })
+                            })
+                            .collect::<Vec<_>>();
+
+                        Ok(WorkshopSearchResults {
+                            items,
+                            total: info.total_results(),
+                        })
+                    }
+                    Err(err) => Err(err.to_string()),
+                };
+
+                let _ = tx.send(result);
+            });
+        });
+
+    async move {
+        result.map_err(|err| format!("Cannot search workshop items: {err}"))?;
+        rx.await.map_err(|_| "Query callback was dropped".to_string())?
+    }
+}
+
 /// Tauri event to request a wokrshop item.
 /// ```ts
 /// import { emit, listen } from "@tauri-apps/api/event";
@@ -52,37 +280,17 @@ pub(crate) fn need_workshop_item<R: Runtime>(handle: tauri::AppHandle<R>, event:
         return;
     };
 
-    let handle = handle.app_handle();
-    let result = client
-        .ugc()
-        .query_item(PublishedFileId(id))
-        .map(move |query| {
-            query.fetch(move |x| {
-                let Ok(info) = x else {
-                    let _ = x.inspect_err(|err| error!("{err}"));
-                    return;
-                };
-
-                let preview = info.preview_url(0);
-                let item = info.get(0).map(|item| WokrshopItem {
-                    id: item.published_file_id.0,
-                    name: item.title,
-                    description: Some(item.description),
-                    preview,
-                });
-
-                if item.is_none() {
-                    error!("need-wokrshop-item item is null {}", event.id());
-                    return;
-                }
+    let ugc = client.ugc();
+    let event_id = event.id();
 
+    tauri::async_runtime::spawn(async move {
+        match query_workshop_item(ugc, PublishedFileId(id)).await {
+            Ok(item) => {
                 let _ = handle.emit_all("got-wokrshop-item", item);
-            });
-        });
-
-    if let Err(err) = result {
-        error!("{err}");
-    }
+            }
+            Err(err) => error!("need-wokrshop-item {err} {event_id}"),
+        }
+    });
 }
 
 /// Get a wokrshop item by id.
@@ -96,59 +304,203 @@ pub(crate) async fn get_workshop_item<R: Runtime>(
     app: tauri::AppHandle<R>,
     id: u64,
 ) -> Result<WokrshopItem, String> {
+    let ugc = {
+        let state = app.state::<SteamWorks>();
+        let client = state.client.lock().map_err(|_| "client is null".to_string())?;
+        client.ugc()
+    };
+
+    query_workshop_item(ugc, PublishedFileId(id)).await
+}
+
+/// Get a workshop collection's child items by the collection's id.
+/// ```ts
+/// import { invoke } from "@tauri-apps/api";
+///
+/// await invoke("get-workshop-collection", { id: 1337 });
+/// ```
+#[tauri::command]
+pub(crate) async fn get_workshop_collection<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    id: u64,
+) -> Result<Vec<WokrshopItem>, String> {
+    let ugc = {
+        let state = app.state::<SteamWorks>();
+        let client = state.client.lock().map_err(|_| "client is null".to_string())?;
+        client.ugc()
+    };
+
+    let children = query_workshop_collection_children(ugc.clone(), PublishedFileId(id)).await?;
+
+    if children.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut items = Vec::with_capacity(children.len());
+    for batch in children.chunks(MAX_QUERY_ITEMS_BATCH) {
+        items.extend(query_workshop_items(ugc.clone(), batch.to_vec()).await?);
+    }
+
+    Ok(items)
+}
+
+/// Search the workshop by text and tags, paginated.
+/// ```ts
+/// import { invoke } from "@tauri-apps/api";
+///
+/// await invoke("search-workshop-items", {
+///     query: { text: "naval", tags: ["Submod"], matchAnyTag: false, sort: "TextRelevance", page: 1 },
+/// });
+/// ```
+#[tauri::command]
+pub(crate) async fn search_workshop_items<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    query: WorkshopSearchQuery,
+) -> Result<WorkshopSearchResults, String> {
+    let (ugc, app_id) = {
+        let state = app.state::<SteamWorks>();
+        let client = state.client.lock().map_err(|_| "client is null".to_string())?;
+        (client.ugc(), AppId(state.app_id))
+    };
+
+    query_workshop_search(ugc, app_id, query).await
+}
+
+/// Subscribe to a workshop item.
+/// ```ts
+/// import { invoke } from "@tauri-apps/api";
+///
+/// await invoke("subscribe-workshop-item", { id: 1337 });
+/// ```
+#[tauri::command]
+pub(crate) fn subscribe_workshop_item<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    id: u64,
+) -> Result<(), String> {
     let state = app.state::<SteamWorks>();
-    let condvar = Arc::new((
-        Mutex::<Option<Result<WokrshopItem, String>>>::new(None),
-        Condvar::new(),
-    ));
-
-    let result = if let Ok(client) = state.client.lock() {
-        let condvar = condvar.clone();
-        client
-            .ugc()
-            .query_item(PublishedFileId(id))
-            .map(move |query| {
-                query.fetch(move |x| {
-                    let Ok(info) = x else {
-                        let _ = x.inspect_err(move |err| {
-                            *condvar.0.lock().unwrap() = Some(Err(err.to_string()));
-                            condvar.1.notify_all();
-                        });
-                        return;
-                    };
+    let client = state.client.lock().map_err(|_| "client is null".to_string())?;
 
-                    let preview = info.preview_url(0);
-                    let item = info.get(0).map(|item| WokrshopItem {
-                        id: item.published_file_id.0,
-                        name: item.title,
-                        description: Some(item.description),
-                        preview,
-                    });
-
-                    if item.is_none() {
-                        *condvar.0.lock().unwrap() = Some(Err("item is null".to_string()));
-                        condvar.1.notify_all();
-                        return;
-                    }
+    client.ugc().subscribe_item(PublishedFileId(id), |result| {
+        if let Err(err) = result {
+            error!("subscribe-workshop-item failed: {err}");
+        }
+    });
 
-                    *condvar.0.lock().unwrap() = Some(Ok(item.unwrap()));
-                    condvar.1.notify_all();
-                });
-            })
-            .map_err(|err| format!("Cannot query item: {err}"))
-    } else {
-        Err("client is null".to_string())
+    Ok(())
+}
+
+/// Unsubscribe from a workshop item.
+/// ```ts
+/// import { invoke } from "@tauri-apps/api";
+///
+/// await invoke("unsubscribe-workshop-item", { id: 1337 });
+/// ```
+#[tauri::command]
+pub(crate) fn unsubscribe_workshop_item<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    id: u64,
+) -> Result<(), String> {
+    let state = app.state::<SteamWorks>();
+    let client = state.client.lock().map_err(|_| "client is null".to_string())?;
+
+    client.ugc().unsubscribe_item(PublishedFileId(id), |result| {
+        if let Err(err) = result {
+            error!("unsubscribe-workshop-item failed: {err}");
+        }
+    });
+
+    Ok(())
+}
+
+/// Kick off a workshop item download. Progress is streamed to the frontend
+/// as `workshop-download-progress`/`workshop-download-complete` events,
+/// driven from the `run_callbacks` pump in `lib.rs`.
+/// ```ts
+/// import { invoke, listen } from "@tauri-apps/api";
+///
+/// await invoke("download-workshop-item", { id: 1337 });
+///
+/// listen<any>("workshop-download-progress", async (event) => {
+///     console.log(event.payload);
+/// });
+/// ```
+#[tauri::command]
+pub(crate) fn download_workshop_item<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    id: u64,
+) -> Result<(), String> {
+    let state = app.state::<SteamWorks>();
+    let client = state.client.lock().map_err(|_| "client is null".to_string())?;
+
+    let item = PublishedFileId(id);
+    if !client.ugc().download_item(item, true) {
+        return Err("Could not start download".to_string());
+    }
+
+    state
+        .downloads
+        .lock()
+        .map_err(|_| "downloads is null".to_string())?
+        .insert(item);
+
+    Ok(())
+}
+
+/// Polls every in-flight download for progress, emitting
+/// `workshop-download-progress` on each tick and a terminal
+/// `workshop-download-complete` once the item is fully installed.
+pub(crate) fn poll_download_progress<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let state = app.state::<SteamWorks>();
+
+    let Ok(client) = state.client.lock() else {
+        return;
+    };
+
+    let Ok(mut downloads) = state.downloads.lock() else {
+        return;
     };
 
-    result?;
+    downloads.retain(|id| {
+        let item_state = client.ugc().item_state(*id);
+        let still_downloading = item_state.contains(ItemState::DOWNLOADING)
+            || item_state.contains(ItemState::DOWNLOAD_PENDING)
+            || item_state.contains(ItemState::NEEDS_UPDATE);
 
-    let lock = condvar
-        .1
-        .wait_while(condvar.0.lock().unwrap(), |x| x.is_none())
-        .unwrap();
+        if item_state.contains(ItemState::INSTALLED) && !still_downloading {
+            // An item that was already installed when the download was kicked
+            // off never reports download progress, so item_download_info is
+            // None here; fall back to the on-disk size for the terminal event.
+            let size = client
+                .ugc()
+                .item_download_info(*id)
+                .map(|i| i.total)
+                .or_else(|| client.ugc().item_install_info(*id).map(|i| i.size_on_disk))
+                .unwrap_or(0);
 
-    let result = lock.clone();
-    result.unwrap()
+            let progress = WorkshopDownloadProgress {
+                id: id.0,
+                downloaded: size,
+                total: size,
+                state: item_state.bits(),
+            };
+
+            let _ = app.emit_all("workshop-download-complete", progress);
+            false
+        } else {
+            let info = client.ugc().item_download_info(*id);
+            let (downloaded, total) = info.map(|i| (i.current, i.total)).unwrap_or_default();
+
+            let progress = WorkshopDownloadProgress {
+                id: id.0,
+                downloaded,
+                total,
+                state: item_state.bits(),
+            };
+
+            let _ = app.emit_all("workshop-download-progress", progress);
+            true
+        }
+    });
 }
 
 /// Get all wokrshop items that are subscribed and installed. <br>